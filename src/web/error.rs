@@ -1,8 +1,14 @@
 use crate::{
-    db::PoolError,
-    web::{page::WebPage, releases::Search, ErrorPage},
+    db::{Pool, PoolError},
+    web::{
+        page::{impl_webpage, WebPage},
+        releases::{self, Search},
+        ErrorPage,
+    },
 };
 use iron::{status::Status, Handler, IronError, IronResult, Request, Response};
+use semver::{Version, VersionReq};
+use serde::Serialize;
 
 #[derive(Debug, Copy, Clone, thiserror::Error)]
 pub enum Nope {
@@ -22,33 +28,136 @@ pub enum Nope {
     InternalServerError,
 }
 
-impl From<Nope> for IronError {
-    fn from(err: Nope) -> IronError {
-        use iron::status;
-
-        let status = match err {
+impl Nope {
+    /// The HTTP status this variant is reported with, on both the HTML and the
+    /// JSON (RFC 7807) error paths.
+    fn status(&self) -> Status {
+        match self {
             Nope::ResourceNotFound
             | Nope::BuildNotFound
             | Nope::CrateNotFound
             | Nope::OwnerNotFound
             | Nope::VersionNotFound
-            | Nope::NoResults => status::NotFound,
-            Nope::InternalServerError => status::InternalServerError,
+            | Nope::NoResults => Status::NotFound,
+            Nope::InternalServerError => Status::InternalServerError,
+        }
+    }
+
+    /// A stable, machine-readable slug identifying this variant, used as the RFC 7807
+    /// `type` member.
+    fn problem_type(&self) -> &'static str {
+        match self {
+            Nope::ResourceNotFound => "resource-not-found",
+            Nope::BuildNotFound => "build-not-found",
+            Nope::CrateNotFound => "crate-not-found",
+            Nope::OwnerNotFound => "owner-not-found",
+            Nope::VersionNotFound => "version-not-found",
+            Nope::NoResults => "no-results",
+            Nope::InternalServerError => "internal-server-error",
+        }
+    }
+
+    /// Renders this error as an `application/problem+json` body (RFC 7807), for
+    /// clients that asked for JSON instead of the HTML error pages.
+    fn as_problem_details(&self, req: &Request) -> IronResult<Response> {
+        let problem = ProblemDetails {
+            type_: self.problem_type(),
+            title: self.to_string(),
+            status: self.status().to_u16(),
+            detail: None,
+            instance: Some(req.url.as_ref().path().to_string()),
         };
 
+        let body = serde_json::to_string(&problem)
+            .map_err(|err| IronError::new(err, Status::InternalServerError))?;
+
+        let mut response = Response::with((self.status(), body));
+        response
+            .headers
+            .set(iron::headers::ContentType("application/problem+json".parse().unwrap()));
+        Ok(response)
+    }
+}
+
+/// Whether the client's `Accept` header prefers a JSON error body over HTML, e.g. a
+/// script or CI job hitting a doc URL directly instead of a browser.
+fn prefers_problem_json(req: &Request) -> bool {
+    let Some(values) = req.headers.get_raw("Accept") else {
+        return false;
+    };
+    let Some(accept) = values.first() else {
+        return false;
+    };
+    let accept = String::from_utf8_lossy(accept);
+
+    let mut html_q: Option<f32> = None;
+    let mut json_q: Option<f32> = None;
+
+    for media_range in accept.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let mut parts = media_range.split(';');
+        let media_type = parts.next().unwrap_or("").trim();
+        let q = parts
+            .filter_map(|param| param.trim().strip_prefix("q="))
+            .find_map(|value| value.trim().parse::<f32>().ok())
+            .unwrap_or(1.0);
+
+        match media_type {
+            "application/json" | "application/problem+json" => {
+                json_q = Some(json_q.map_or(q, |existing| existing.max(q)));
+            }
+            "text/html" | "application/xhtml+xml" | "*/*" => {
+                html_q = Some(html_q.map_or(q, |existing| existing.max(q)));
+            }
+            _ => {}
+        }
+    }
+
+    // Only prefer JSON when the client actually asked for it with a strictly higher
+    // priority than HTML (ties go to HTML), or with any nonzero priority when HTML
+    // wasn't offered at all - "Accept: text/html, application/json;q=0.1" should still
+    // render the HTML error page.
+    match (json_q, html_q) {
+        (Some(json_q), Some(html_q)) => json_q > html_q,
+        (Some(json_q), None) => json_q > 0.0,
+        (None, _) => false,
+    }
+}
+
+/// RFC 7807 problem-details body for non-HTML clients.
+#[derive(Debug, Serialize)]
+struct ProblemDetails {
+    #[serde(rename = "type")]
+    type_: &'static str,
+    title: String,
+    status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    instance: Option<String>,
+}
+
+impl From<Nope> for IronError {
+    fn from(err: Nope) -> IronError {
+        let status = err.status();
         IronError::new(err, status)
     }
 }
 
 impl Handler for Nope {
     fn handle(&self, req: &mut Request) -> IronResult<Response> {
+        if prefers_problem_json(req) {
+            return self.as_problem_details(req);
+        }
+
         match *self {
             Nope::ResourceNotFound => {
                 // user tried to navigate to a resource (doc page/file) that doesn't exist
-                // TODO: Display the attempted page
-                ErrorPage {
+                let attempted_path = req.url.as_ref().path().to_string();
+
+                ResourceNotFoundPage {
                     title: "The requested resource does not exist",
-                    message: Some("no such resource".into()),
+                    attempted_path,
+                    resolution: resolve_resource(req),
                     status: Status::NotFound,
                 }
                 .into_response(req)
@@ -63,10 +172,13 @@ impl Handler for Nope {
 
             Nope::CrateNotFound => {
                 // user tried to navigate to a crate that doesn't exist
-                // TODO: Display the attempted crate and a link to a search for said crate
-                ErrorPage {
+                let attempted_crate = attempted_crate_name(req);
+                let suggestions = suggest_crates(req, &attempted_crate).unwrap_or_default();
+
+                CrateNotFoundPage {
                     title: "The requested crate does not exist",
-                    message: Some("no such crate".into()),
+                    attempted_crate,
+                    suggestions,
                     status: Status::NotFound,
                 }
                 .into_response(req)
@@ -81,10 +193,19 @@ impl Handler for Nope {
 
             Nope::VersionNotFound => {
                 // user tried to navigate to a crate with a version that does not exist
-                // TODO: Display the attempted crate and version
-                ErrorPage {
+                let (crate_name, attempted_version) = attempted_crate_and_version(req);
+                let suggestion = suggest_version(req, &crate_name, &attempted_version);
+                let all_yanked = suggestion
+                    .as_ref()
+                    .map(|s| s.all_yanked)
+                    .unwrap_or(false);
+
+                VersionNotFoundPage {
                     title: "The requested version does not exist",
-                    message: Some("no such version for this crate".into()),
+                    crate_name,
+                    attempted_version,
+                    suggestion: suggestion.map(|s| s.version),
+                    all_yanked,
                     status: Status::NotFound,
                 }
                 .into_response(req)
@@ -132,10 +253,382 @@ impl From<PoolError> for IronError {
     }
 }
 
+/// The crate-not-found page, with a list of "did you mean ...?" suggestions for the
+/// crate name the user actually tried to reach.
+#[derive(Debug, Clone, Serialize)]
+struct CrateNotFoundPage {
+    title: &'static str,
+    attempted_crate: String,
+    suggestions: Vec<releases::CrateHit>,
+    #[serde(skip)]
+    status: Status,
+}
+
+impl_webpage!(CrateNotFoundPage = "core/crate_not_found.html");
+
+/// Pulls the crate name the visitor actually tried to reach out of the request path.
+fn attempted_crate_name(req: &Request) -> String {
+    req.url
+        .as_ref()
+        .path_segments()
+        .and_then(|mut segments| segments.next())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Finds near-miss crate names for `attempted`, for the "did you mean ...?" suggestions
+/// on the crate-not-found page.
+///
+/// Candidates come from [`releases::search_similar_crate_names`], filtered down to the
+/// ones within a small edit distance of `attempted` (scaling with its length) or sharing
+/// a common prefix with it, then sorted by edit distance and, as a tie-breaker, by
+/// download count. The result is capped at 8 entries.
+fn suggest_crates(req: &Request, attempted: &str) -> Option<Vec<releases::CrateHit>> {
+    if attempted.is_empty() {
+        return None;
+    }
+
+    let pool = req.extensions().get::<Pool>()?.clone();
+    let mut conn = pool.get().ok()?;
+
+    let candidates = releases::search_similar_crate_names(&mut conn, attempted, 50).ok()?;
+    let threshold = (attempted.len() / 3).max(2);
+
+    let mut suggestions: Vec<_> = candidates
+        .into_iter()
+        .map(|candidate| {
+            let distance = levenshtein_distance(attempted, &candidate.name);
+            (distance, candidate)
+        })
+        .filter(|(distance, candidate)| {
+            *distance <= threshold
+                || candidate.name.starts_with(attempted)
+                || attempted.starts_with(candidate.name.as_str())
+        })
+        .collect();
+
+    suggestions.sort_by(|(distance_a, a), (distance_b, b)| {
+        distance_a
+            .cmp(distance_b)
+            .then(b.downloads.cmp(&a.downloads))
+    });
+    suggestions.truncate(8);
+
+    Some(
+        suggestions
+            .into_iter()
+            .map(|(_, candidate)| candidate)
+            .collect(),
+    )
+}
+
+/// Computes the Levenshtein (edit) distance between two strings.
+fn levenshtein_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, &char_a) in a.iter().enumerate() {
+        let mut prev_diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &char_b) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if char_a == char_b {
+                prev_diagonal
+            } else {
+                1 + prev_diagonal.min(above).min(row[j])
+            };
+            prev_diagonal = above;
+        }
+    }
+
+    row[b.len()]
+}
+
+/// The version-not-found page, offering a clickable link to the closest available
+/// version instead of a dead end.
+#[derive(Debug, Clone, Serialize)]
+struct VersionNotFoundPage {
+    title: &'static str,
+    crate_name: String,
+    attempted_version: String,
+    suggestion: Option<String>,
+    all_yanked: bool,
+    #[serde(skip)]
+    status: Status,
+}
+
+impl_webpage!(VersionNotFoundPage = "core/version_not_found.html");
+
+/// A suggested version to fall back to, together with whether every release of the
+/// crate turned out to be yanked.
+struct VersionSuggestion {
+    version: String,
+    all_yanked: bool,
+}
+
+/// A release version and its yanked status, as loaded for [`suggest_version`].
+struct ReleaseVersion {
+    version: Version,
+    yanked: bool,
+}
+
+/// Pulls the crate name and the unparseable/nonexistent version string out of the
+/// request path, e.g. `/dummy/not-semver` -> `("dummy", "not-semver")`.
+fn attempted_crate_and_version(req: &Request) -> (String, String) {
+    let mut segments = req
+        .url
+        .as_ref()
+        .path_segments()
+        .map(|segments| segments.collect::<Vec<_>>())
+        .unwrap_or_default()
+        .into_iter();
+
+    let crate_name = segments.next().unwrap_or_default().to_string();
+    let attempted_version = segments.next().unwrap_or_default().to_string();
+
+    (crate_name, attempted_version)
+}
+
+/// Finds the closest available version of `crate_name` to suggest in place of
+/// `attempted`.
+///
+/// `attempted` is parsed as a semver *requirement* (via [`VersionReq`]), not a bare
+/// version, since most broken version segments in the wild are partial (`2.0`, `1`,
+/// `*`, `^1`) rather than a full `X.Y.Z` - parsing those with [`Version`] alone would
+/// reject them outright and skip the matching logic entirely. The highest non-yanked
+/// release satisfying that requirement is suggested; if none satisfy it but `attempted`
+/// does happen to be a full version, this falls back to the highest release `<=`
+/// attempted. Otherwise (or if nothing matched), it falls back to the latest non-yanked
+/// release. If every release of the crate is yanked, the latest yanked release is
+/// suggested instead, with `all_yanked` set so the page can say so explicitly.
+fn suggest_version(req: &Request, crate_name: &str, attempted: &str) -> Option<VersionSuggestion> {
+    if crate_name.is_empty() {
+        return None;
+    }
+
+    let pool = req.extensions().get::<Pool>()?.clone();
+    let mut conn = pool.get().ok()?;
+
+    let releases = query_release_versions(&mut conn, crate_name).ok()?;
+    suggest_version_from_releases(&releases, attempted)
+}
+
+/// The comparison logic behind [`suggest_version`], split out so callers that already
+/// loaded a crate's releases (like [`resolve_resource`]) don't have to query again.
+fn suggest_version_from_releases(releases: &[ReleaseVersion], attempted: &str) -> Option<VersionSuggestion> {
+    if releases.is_empty() {
+        return None;
+    }
+
+    let all_yanked = releases.iter().all(|release| release.yanked);
+
+    if let Ok(requirement) = VersionReq::parse(attempted) {
+        let satisfying = releases
+            .iter()
+            .filter(|release| !release.yanked && requirement.matches(&release.version))
+            .max_by(|a, b| a.version.cmp(&b.version));
+
+        if let Some(release) = satisfying {
+            return Some(VersionSuggestion {
+                version: release.version.to_string(),
+                all_yanked: false,
+            });
+        }
+    }
+
+    if let Ok(requested) = Version::parse(attempted) {
+        let highest_leq = releases
+            .iter()
+            .filter(|release| !release.yanked && release.version <= requested)
+            .max_by(|a, b| a.version.cmp(&b.version));
+
+        if let Some(release) = highest_leq {
+            return Some(VersionSuggestion {
+                version: release.version.to_string(),
+                all_yanked: false,
+            });
+        }
+    }
+
+    if all_yanked {
+        let latest_yanked = releases.iter().max_by(|a, b| a.version.cmp(&b.version))?;
+        return Some(VersionSuggestion {
+            version: latest_yanked.version.to_string(),
+            all_yanked: true,
+        });
+    }
+
+    let latest = releases
+        .iter()
+        .filter(|release| !release.yanked)
+        .max_by(|a, b| a.version.cmp(&b.version))?;
+
+    Some(VersionSuggestion {
+        version: latest.version.to_string(),
+        all_yanked: false,
+    })
+}
+
+/// The resource-not-found page: a broken deep-link into a crate's docs (common after a
+/// crate restructures its module paths between versions), together with whatever we
+/// could recover about where the visitor was actually trying to go.
+#[derive(Debug, Clone, Serialize)]
+struct ResourceNotFoundPage {
+    title: &'static str,
+    attempted_path: String,
+    resolution: ResourceResolution,
+    #[serde(skip)]
+    status: Status,
+}
+
+impl_webpage!(ResourceNotFoundPage = "core/resource_not_found.html");
+
+/// What we managed to figure out about a broken resource path.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind")]
+enum ResourceResolution {
+    /// The crate and version both exist; link to its documentation root and file
+    /// listing instead of the missing page.
+    KnownRelease { crate_name: String, version: String },
+    /// The crate itself doesn't exist; degrade to the CrateNotFound suggestion flow.
+    UnknownCrate {
+        suggestions: Vec<releases::CrateHit>,
+    },
+    /// The crate exists but not at that version; degrade to the VersionNotFound
+    /// suggestion flow.
+    UnknownVersion {
+        crate_name: String,
+        suggestion: Option<String>,
+        all_yanked: bool,
+    },
+    /// The path didn't look like it belonged to a crate at all.
+    Unknown,
+}
+
+/// Attempts to resolve a broken resource path (e.g. `/crate/version/.../missing.html`)
+/// to a known crate and version, falling back to the CrateNotFound/VersionNotFound
+/// suggestion flows when the crate or version in the path is itself unknown.
+fn resolve_resource(req: &Request) -> ResourceResolution {
+    let segments = req
+        .url
+        .as_ref()
+        .path_segments()
+        .map(|segments| segments.collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    let (Some(crate_name), Some(attempted_version)) = (segments.first(), segments.get(1)) else {
+        return ResourceResolution::Unknown;
+    };
+
+    // A pool/connection failure here is a backend hiccup, not evidence the crate is
+    // missing, so it degrades to `Unknown` rather than the crate-not-found flow.
+    let Some(pool) = req.extensions().get::<Pool>().cloned() else {
+        return ResourceResolution::Unknown;
+    };
+    let Ok(mut conn) = pool.get() else {
+        return ResourceResolution::Unknown;
+    };
+    let Ok(releases) = query_release_versions(&mut conn, crate_name) else {
+        return ResourceResolution::Unknown;
+    };
+
+    if releases.is_empty() {
+        let suggestions = suggest_crates(req, crate_name).unwrap_or_default();
+        return ResourceResolution::UnknownCrate { suggestions };
+    }
+
+    let version_exists = Version::parse(attempted_version)
+        .map(|version| releases.iter().any(|r| r.version == version))
+        .unwrap_or(false);
+
+    if version_exists {
+        return ResourceResolution::KnownRelease {
+            crate_name: crate_name.to_string(),
+            version: attempted_version.to_string(),
+        };
+    }
+
+    let suggestion = suggest_version_from_releases(&releases, attempted_version);
+    ResourceResolution::UnknownVersion {
+        crate_name: crate_name.to_string(),
+        all_yanked: suggestion.as_ref().map(|s| s.all_yanked).unwrap_or(false),
+        suggestion: suggestion.map(|s| s.version),
+    }
+}
+
+/// Loads every known release of `crate_name`, parsing each version as semver and
+/// skipping ones that don't parse (they can't be meaningfully compared anyway).
+fn query_release_versions(
+    conn: &mut postgres::Client,
+    crate_name: &str,
+) -> Result<Vec<ReleaseVersion>, postgres::Error> {
+    let rows = conn.query(
+        "SELECT releases.version, releases.yanked
+         FROM releases
+         INNER JOIN crates ON releases.crate_id = crates.id
+         WHERE crates.name = $1",
+        &[&crate_name],
+    )?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| {
+            let version: String = row.get("version");
+            let yanked: bool = row.get("yanked");
+            Version::parse(&version).ok().map(|version| ReleaseVersion { version, yanked })
+        })
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::test::wrapper;
     use kuchiki::traits::TendrilSink;
+    use serde_json::Value;
+
+    #[test]
+    fn check_404_page_json_problem_details() {
+        wrapper(|env| {
+            let response = env
+                .frontend()
+                .get("/crate-which-doesnt-exist")
+                .header("Accept", "application/json")
+                .send()?;
+
+            assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+            let body: Value = response.json()?;
+            assert_eq!(body["type"], "crate-not-found");
+            assert_eq!(body["status"], 404);
+            assert_eq!(body["title"], "Requested crate not found");
+            assert_eq!(body["instance"], "/crate-which-doesnt-exist");
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn check_404_page_json_not_preferred_over_html() {
+        wrapper(|env| {
+            let response = env
+                .frontend()
+                .get("/crate-which-doesnt-exist")
+                .header("Accept", "text/html, application/json;q=0.1")
+                .send()?;
+
+            assert_eq!(
+                response
+                    .headers()
+                    .get(reqwest::header::CONTENT_TYPE)
+                    .and_then(|value| value.to_str().ok())
+                    .map(|value| value.contains("html")),
+                Some(true),
+            );
+
+            Ok(())
+        });
+    }
 
     #[test]
     fn check_404_page_content_crate() {
@@ -160,6 +653,24 @@ mod tests {
         });
     }
 
+    #[test]
+    fn check_404_page_content_crate_suggestions() {
+        wrapper(|env| {
+            env.fake_release().name("regex").create()?;
+            let page =
+                kuchiki::parse_html().one(env.frontend().get("/regexx").send()?.text()?);
+            assert_eq!(
+                page.select("#crate-suggestions a")
+                    .unwrap()
+                    .map(|el| el.text_contents())
+                    .collect::<Vec<_>>(),
+                vec!["regex".to_string()],
+            );
+
+            Ok(())
+        });
+    }
+
     #[test]
     fn check_404_page_content_resource() {
         wrapper(|env| {
@@ -203,6 +714,27 @@ mod tests {
         });
     }
 
+    #[test]
+    fn check_404_page_content_version_suggestion_partial_version() {
+        wrapper(|env| {
+            env.fake_release().name("dummy").version("1.0.0").create()?;
+            env.fake_release().name("dummy").version("2.0.0").create()?;
+            env.fake_release().name("dummy").version("3.0.0").create()?;
+
+            let page = kuchiki::parse_html().one(env.frontend().get("/dummy/2.0").send()?.text()?);
+            assert_eq!(
+                page.select("#version-suggestion")
+                    .unwrap()
+                    .next()
+                    .unwrap()
+                    .text_contents(),
+                "2.0.0",
+            );
+
+            Ok(())
+        });
+    }
+
     #[test]
     fn check_404_page_content_nonexistent_version() {
         wrapper(|env| {
@@ -244,4 +776,54 @@ mod tests {
             Ok(())
         });
     }
+
+    #[test]
+    fn check_404_page_content_resource_known_release() {
+        wrapper(|env| {
+            env.fake_release().name("dummy").version("1.0.0").create()?;
+
+            let page = kuchiki::parse_html().one(
+                env.frontend()
+                    .get("/dummy/1.0.0/missing.html")
+                    .send()?
+                    .text()?,
+            );
+            assert_eq!(
+                page.select("#resource-crate-link")
+                    .unwrap()
+                    .next()
+                    .unwrap()
+                    .attributes
+                    .borrow()
+                    .get("href")
+                    .unwrap(),
+                "/dummy/1.0.0/dummy/",
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn check_404_page_content_resource_unknown_crate_suggestions() {
+        wrapper(|env| {
+            env.fake_release().name("regex").create()?;
+
+            let page = kuchiki::parse_html().one(
+                env.frontend()
+                    .get("/regexx/1.0.0/missing.html")
+                    .send()?
+                    .text()?,
+            );
+            assert_eq!(
+                page.select("#crate-suggestions a")
+                    .unwrap()
+                    .map(|el| el.text_contents())
+                    .collect::<Vec<_>>(),
+                vec!["regex".to_string()],
+            );
+
+            Ok(())
+        });
+    }
 }
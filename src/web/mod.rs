@@ -0,0 +1,32 @@
+pub(crate) mod error;
+pub(crate) mod page;
+pub(crate) mod releases;
+
+pub use error::Nope;
+
+use iron::status::Status;
+use serde::Serialize;
+
+use crate::web::page::impl_webpage;
+
+/// The Tera template engine, stored as an Iron request extension so [`page::WebPage`]
+/// can render any registered page from anywhere a `&Request` is available.
+pub(crate) struct TemplateData {
+    pub(crate) engine: tera::Tera,
+}
+
+impl iron::typemap::Key for TemplateData {
+    type Value = TemplateData;
+}
+
+/// A generic error page, used where there's nothing more specific to say than the
+/// title and an optional one-line message.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct ErrorPage {
+    pub title: &'static str,
+    pub message: Option<String>,
+    #[serde(skip)]
+    pub status: Status,
+}
+
+impl_webpage!(ErrorPage = "core/error.html");
@@ -0,0 +1,54 @@
+use iron::{headers::ContentType, status::Status, IronError, IronResult, Request, Response};
+use serde::Serialize;
+
+use crate::web::TemplateData;
+
+/// A page that renders itself from a Tera template. Implementors are registered via
+/// [`impl_webpage!`], which fills in [`WebPage::TEMPLATE`] and [`WebPage::status`] from
+/// the struct's own `status: iron::status::Status` field.
+pub(crate) trait WebPage: Serialize + Sized {
+    /// The Tera template this page renders through.
+    const TEMPLATE: &'static str;
+
+    /// The HTTP status the response should carry.
+    fn status(&self) -> Status;
+
+    fn into_response(self, req: &Request) -> IronResult<Response> {
+        let status = self.status();
+
+        let templates = req
+            .extensions()
+            .get::<TemplateData>()
+            .expect("missing template engine extension");
+
+        let context = tera::Context::from_serialize(&self)
+            .map_err(|err| IronError::new(err, Status::InternalServerError))?;
+
+        let rendered = templates
+            .engine
+            .render(Self::TEMPLATE, &context)
+            .map_err(|err| IronError::new(err, Status::InternalServerError))?;
+
+        let mut response = Response::with((status, rendered));
+        response.headers.set(ContentType::html());
+        Ok(response)
+    }
+}
+
+/// Registers a page struct's Tera template, implementing [`WebPage`] for it.
+///
+/// The struct is expected to carry a `status: iron::status::Status` field, which
+/// becomes [`WebPage::status`].
+macro_rules! impl_webpage {
+    ($page:ty = $template:expr) => {
+        impl $crate::web::page::WebPage for $page {
+            const TEMPLATE: &'static str = $template;
+
+            fn status(&self) -> ::iron::status::Status {
+                self.status
+            }
+        }
+    };
+}
+
+pub(crate) use impl_webpage;
@@ -0,0 +1,75 @@
+use iron::status::Status;
+use serde::Serialize;
+
+use crate::web::page::impl_webpage;
+
+/// The crate search results page, also used as the landing page for a plain-text
+/// query with no results.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct Search {
+    pub title: String,
+    pub search_query: Option<String>,
+    #[serde(skip)]
+    pub status: Status,
+}
+
+impl Default for Search {
+    fn default() -> Self {
+        Search {
+            title: String::new(),
+            search_query: None,
+            status: Status::Ok,
+        }
+    }
+}
+
+impl_webpage!(Search = "releases/search_results.html");
+
+/// A single crate name surfaced by [`search_similar_crate_names`].
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct CrateHit {
+    pub name: String,
+    pub version: String,
+    pub downloads: i64,
+}
+
+/// Finds crates whose name resembles `query`, for the "did you mean ...?" suggestions
+/// on the crate/version/resource not-found pages.
+///
+/// This uses `pg_trgm` trigram similarity (the `%` operator) rather than a substring
+/// match, so a typo like an extra or changed character still turns up the intended
+/// crate — `crates.name ILIKE '%' || $1 || '%'` would require the *misspelled* string
+/// to appear verbatim inside the real name, which a typo by definition doesn't satisfy.
+/// Candidates are deduplicated to one row per crate (keeping its most-downloaded
+/// release) before being handed to the caller's edit-distance ranking.
+pub(crate) fn search_similar_crate_names(
+    conn: &mut postgres::Client,
+    query: &str,
+    limit: i64,
+) -> Result<Vec<CrateHit>, postgres::Error> {
+    let rows = conn.query(
+        "SELECT name, version, downloads FROM (
+             SELECT DISTINCT ON (crates.name)
+                 crates.name AS name,
+                 releases.version AS version,
+                 releases.downloads AS downloads,
+                 similarity(crates.name, $1) AS score
+             FROM crates
+             INNER JOIN releases ON releases.crate_id = crates.id
+             WHERE crates.name % $1
+             ORDER BY crates.name, releases.downloads DESC
+         ) AS candidates
+         ORDER BY score DESC
+         LIMIT $2",
+        &[&query, &limit],
+    )?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| CrateHit {
+            name: row.get("name"),
+            version: row.get("version"),
+            downloads: row.get("downloads"),
+        })
+        .collect())
+}